@@ -1,6 +1,10 @@
 #![no_std]
 
-use embedded_nal::{nb, SocketAddr, TcpClientStack};
+use embedded_nal::{
+    nb, AddrType, Dns, SocketAddr, TcpClientStack, TcpError, TcpErrorKind, UdpClientStack,
+    UdpFullStack,
+};
+use embedded_io::ErrorKind;
 
 pub trait TcpClientStackPlus<TCS: TcpClientStack> {
     /// create a tuple referencing the TcpClientStack and a TcpSocket that has all the information necessary to read and write data.
@@ -17,6 +21,16 @@ impl<TCS: TcpClientStack> TcpClientStackPlus<TCS> for TCS {
     }
 }
 
+/// The error returned by [StackAndSocket::connect_host], distinguishing a failure to resolve
+/// the hostname from a failure to connect to the resolved address.
+#[derive(Debug)]
+pub enum ConnectHostError<DE, TE> {
+    /// the [Dns] lookup failed.
+    Dns(DE),
+    /// the [TcpClientStack] connection attempt failed.
+    Tcp(TE),
+}
+
 /// This tuple combines a reference to the [TcpClientStack] and a [TcpSocket] into a single entity that knows enough to read or write to the socket.
 /// It is intended to be an ephemeral construction, and was primarily motivated as an implementer of [ufmt::uWrite].
 pub struct StackAndSocket<'a, TCS>
@@ -27,6 +41,12 @@ where
     pub tcp_stack: &'a mut TCS,
     /// a [TcpSocket] usable with the [self.stack] to perform I/O.
     pub socket: &'a mut TCS::TcpSocket,
+    /// the most recent `TCS::Error` seen by the [core::fmt::Write] impl, since that trait's
+    /// `write_str` cannot carry an error payload of its own. See [Self::take_error].
+    last_error: Option<TCS::Error>,
+    /// the address resolved by a prior, still-pending call to [Self::connect_host], so a retry
+    /// only re-polls [TcpClientStack::connect] instead of re-resolving the hostname.
+    resolved_host: Option<SocketAddr>,
 }
 
 impl<'a, TCS> StackAndSocket<'a, TCS>
@@ -35,7 +55,20 @@ where
 {
     /// create a new [StackAndSocket] from the stack reference and socket reference
     pub fn new(tcp_stack: &'a mut TCS, socket: &'a mut TCS::TcpSocket) -> Self {
-        StackAndSocket { tcp_stack, socket }
+        StackAndSocket {
+            tcp_stack,
+            socket,
+            last_error: None,
+            resolved_host: None,
+        }
+    }
+
+    /// take the `TCS::Error` stashed by a failed [core::fmt::Write::write_str] call, if any.
+    ///
+    /// `core::fmt::Write::write_str` can only return `fmt::Error`, which carries no payload, so
+    /// a failed write via that trait stores the real stack error here for later recovery.
+    pub fn take_error(&mut self) -> Option<TCS::Error> {
+        self.last_error.take()
     }
 
     /// Connect to the given remote host and port.
@@ -46,9 +79,44 @@ where
         self.tcp_stack.connect(self.socket, remote)
     }
 
-    /// Check if this socket is connected
-    pub fn is_connected(&mut self) -> Result<bool, TCS::Error> {
-        self.tcp_stack.is_connected(self.socket)
+    /// Resolve `host` to a [SocketAddr] using [Dns::get_host_by_name] and connect to it.
+    ///
+    /// `addr_type` controls whether an IPv4 or IPv6 address is requested from the resolver.
+    /// Returns [`nb::Error::WouldBlock`] while either the resolution or the connection attempt
+    /// is still in progress, and a [ConnectHostError] distinguishing DNS failures from TCP
+    /// failures otherwise.
+    ///
+    /// Like [Self::connect], this is meant to be polled until it stops returning `WouldBlock`.
+    /// Unlike `TcpClientStack::connect`, `Dns::get_host_by_name` carries no handle to poll an
+    /// in-progress lookup by, so the resolved address is cached on `self` once DNS succeeds;
+    /// a later call only retries [TcpClientStack::connect] instead of resolving `host` again.
+    pub fn connect_host(
+        &mut self,
+        host: &str,
+        port: u16,
+        addr_type: AddrType,
+    ) -> nb::Result<(), ConnectHostError<<TCS as Dns>::Error, <TCS as TcpClientStack>::Error>>
+    where
+        TCS: Dns,
+    {
+        let remote = match self.resolved_host {
+            Some(remote) => remote,
+            None => {
+                let ip = Dns::get_host_by_name(self.tcp_stack, host, addr_type)
+                    .map_err(|e| e.map(ConnectHostError::Dns))?;
+                let remote = SocketAddr::new(ip, port);
+                self.resolved_host = Some(remote);
+                remote
+            }
+        };
+        let result = self
+            .tcp_stack
+            .connect(self.socket, remote)
+            .map_err(|e| e.map(ConnectHostError::Tcp));
+        if !matches!(result, Err(nb::Error::WouldBlock)) {
+            self.resolved_host = None;
+        }
+        result
     }
 
     /// Receive data from the stream.
@@ -87,11 +155,919 @@ where
     }
 }
 
+impl<'a, TCS> core::fmt::Write for StackAndSocket<'a, TCS>
+where
+    TCS: TcpClientStack,
+{
+    /// Write `message` to the stream, blocking until it has all been sent.
+    ///
+    /// Unlike [ufmt::uWrite::write_str], this returns a bare [core::fmt::Error] on failure; the
+    /// underlying `TCS::Error`, if any, is stashed and can be retrieved with [Self::take_error].
+    fn write_str(&mut self, message: &str) -> core::fmt::Result {
+        let message = message.as_bytes();
+        let mut cursor = 0;
+        while cursor < message.len() {
+            match nb::block!(self.tcp_stack.send(self.socket, &message[cursor..])) {
+                Ok(n) => cursor += n,
+                Err(e) => {
+                    self.last_error = Some(e);
+                    return Err(core::fmt::Error);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error returned by [StackAndSocket::send_all], distinguishing a peer-closed connection
+/// (after which the socket should be torn down and reconnected) from any other send failure.
+#[derive(Debug)]
+pub enum SendAllError<E> {
+    /// the peer closed the connection; tear down this socket and reconnect.
+    Closed,
+    /// some other, presumably transient or fatal, `TCS::Error`.
+    Other(E),
+}
+
+impl<'a, TCS> StackAndSocket<'a, TCS>
+where
+    TCS: TcpClientStack,
+    TCS::Error: TcpError,
+{
+    /// Write the entirety of `buffer`, blocking until it has all been sent.
+    ///
+    /// Unlike [Self::send], this classifies a bounded `TCS::Error` via [TcpError::kind]: a
+    /// peer-closed connection is reported as [SendAllError::Closed] rather than bubbling up the
+    /// raw stack error, so callers can tell "reconnect" apart from "something else went wrong".
+    pub fn send_all(&mut self, buffer: &[u8]) -> Result<(), SendAllError<TCS::Error>> {
+        let mut cursor = 0;
+        while cursor < buffer.len() {
+            match self.tcp_stack.send(self.socket, &buffer[cursor..]) {
+                Ok(n) => cursor += n,
+                Err(nb::Error::WouldBlock) => continue,
+                Err(nb::Error::Other(e)) => {
+                    return Err(match e.kind() {
+                        TcpErrorKind::PipeClosed => SendAllError::Closed,
+                        _ => SendAllError::Other(e),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check whether the peer has closed the connection, using the same [TcpError]
+    /// classification as [Self::send_all].
+    ///
+    /// This probes liveness with a zero-length `send`, since `TcpClientStack` has no dedicated
+    /// status query. Stacks that special-case a zero-length write as a no-op `Ok(0)`, without
+    /// touching the transport, will report `false` even on a peer-closed socket; prefer checking
+    /// the error returned by a real [Self::send]/[Self::send_all] when that's available instead.
+    pub fn is_closed(&mut self) -> bool {
+        match self.tcp_stack.send(self.socket, &[]) {
+            Err(nb::Error::Other(e)) => matches!(e.kind(), TcpErrorKind::PipeClosed),
+            _ => false,
+        }
+    }
+}
+
+/// How [StackAndSocket::receive_mode]/[StackAndSocket::send_mode] should wait for an I/O
+/// operation to complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// return immediately, same as [StackAndSocket::receive]/[StackAndSocket::send].
+    NonBlocking,
+    /// loop until the operation completes.
+    Blocking,
+    /// loop until the operation completes or this many milliseconds have elapsed.
+    Timeout(u32),
+}
+
+/// The error returned by [StackAndSocket::receive_mode]/[StackAndSocket::send_mode].
+#[derive(Debug)]
+pub enum ModeError<E> {
+    /// [Mode::NonBlocking] was requested and the operation did not complete immediately.
+    WouldBlock,
+    /// [Mode::Timeout] elapsed before the operation completed.
+    TimedOut,
+    /// some other `TCS::Error`.
+    Other(E),
+}
+
+impl<'a, TCS> StackAndSocket<'a, TCS>
+where
+    TCS: TcpClientStack,
+{
+    /// Receive data from the stream, waiting according to `mode`.
+    ///
+    /// `clock_ms` is a caller-supplied monotonic millisecond clock; it is only consulted when
+    /// `mode` is [Mode::Timeout] and may be a no-op closure otherwise.
+    pub fn receive_mode<F: FnMut() -> u64>(
+        &mut self,
+        buffer: &mut [u8],
+        mode: Mode,
+        mut clock_ms: F,
+    ) -> Result<usize, ModeError<TCS::Error>> {
+        match mode {
+            Mode::NonBlocking => {
+                self.tcp_stack
+                    .receive(self.socket, buffer)
+                    .map_err(|e| match e {
+                        nb::Error::WouldBlock => ModeError::WouldBlock,
+                        nb::Error::Other(e) => ModeError::Other(e),
+                    })
+            }
+            Mode::Blocking => nb::block!(self.tcp_stack.receive(self.socket, buffer))
+                .map_err(ModeError::Other),
+            Mode::Timeout(timeout_ms) => {
+                let deadline = clock_ms().wrapping_add(timeout_ms as u64);
+                loop {
+                    match self.tcp_stack.receive(self.socket, buffer) {
+                        Ok(n) => return Ok(n),
+                        Err(nb::Error::Other(e)) => return Err(ModeError::Other(e)),
+                        Err(nb::Error::WouldBlock) => {
+                            if clock_ms() >= deadline {
+                                return Err(ModeError::TimedOut);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Write to the stream, waiting according to `mode`.
+    ///
+    /// `clock_ms` is a caller-supplied monotonic millisecond clock; it is only consulted when
+    /// `mode` is [Mode::Timeout] and may be a no-op closure otherwise.
+    pub fn send_mode<F: FnMut() -> u64>(
+        &mut self,
+        buffer: &[u8],
+        mode: Mode,
+        mut clock_ms: F,
+    ) -> Result<usize, ModeError<TCS::Error>> {
+        match mode {
+            Mode::NonBlocking => {
+                self.tcp_stack
+                    .send(self.socket, buffer)
+                    .map_err(|e| match e {
+                        nb::Error::WouldBlock => ModeError::WouldBlock,
+                        nb::Error::Other(e) => ModeError::Other(e),
+                    })
+            }
+            Mode::Blocking => {
+                nb::block!(self.tcp_stack.send(self.socket, buffer)).map_err(ModeError::Other)
+            }
+            Mode::Timeout(timeout_ms) => {
+                let deadline = clock_ms().wrapping_add(timeout_ms as u64);
+                loop {
+                    match self.tcp_stack.send(self.socket, buffer) {
+                        Ok(n) => return Ok(n),
+                        Err(nb::Error::Other(e)) => return Err(ModeError::Other(e)),
+                        Err(nb::Error::WouldBlock) => {
+                            if clock_ms() >= deadline {
+                                return Err(ModeError::TimedOut);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Wraps a [StackAndSocket] with a fixed-size staging buffer so that small, repeated
+/// `write_str`/`write_char` calls (as produced by `ufmt`/`write!` formatting) are coalesced
+/// into fewer, larger calls to [TcpClientStack::send]. This matters on stacks where each
+/// `send` is an expensive round trip, such as AT-modem based implementations.
+///
+/// Callers must call [Self::flush] before dropping a `BufferedStackAndSocket`; any bytes
+/// still sitting in the accumulator are otherwise never sent.
+pub struct BufferedStackAndSocket<'a, TCS, const N: usize>
+where
+    TCS: TcpClientStack,
+{
+    inner: StackAndSocket<'a, TCS>,
+    buffer: [u8; N],
+    len: usize,
+}
+
+impl<'a, TCS, const N: usize> BufferedStackAndSocket<'a, TCS, N>
+where
+    TCS: TcpClientStack,
+{
+    /// wrap a [StackAndSocket] with an `N`-byte accumulator.
+    ///
+    /// `N` must be greater than zero; with no room to stage anything, [Self::stage] would spin
+    /// forever trying to make space for bytes that will never fit.
+    pub fn new(inner: StackAndSocket<'a, TCS>) -> Self {
+        assert!(N > 0, "BufferedStackAndSocket requires a non-zero buffer size");
+        BufferedStackAndSocket {
+            inner,
+            buffer: [0; N],
+            len: 0,
+        }
+    }
+
+    /// the number of bytes currently staged in the accumulator and not yet sent.
+    pub fn bytes_buffered(&self) -> usize {
+        self.len
+    }
+
+    /// send the contents of the accumulator until it is empty.
+    ///
+    /// Returns [`nb::Error::WouldBlock`] if the underlying socket could not accept all of the
+    /// buffered bytes yet; callers should retry until this returns `Ok(())`.
+    pub fn flush(&mut self) -> nb::Result<(), TCS::Error> {
+        while self.len > 0 {
+            let n = self.inner.send(&self.buffer[..self.len])?;
+            self.buffer.copy_within(n..self.len, 0);
+            self.len -= n;
+        }
+        Ok(())
+    }
+
+    /// drain a blocking flush, retrying on [`nb::Error::WouldBlock`].
+    fn try_flush_sendbuffer(&mut self) -> Result<(), TCS::Error> {
+        nb::block!(self.flush())
+    }
+
+    fn stage(&mut self, bytes: &[u8]) -> Result<(), TCS::Error> {
+        let mut cursor = 0;
+        while cursor < bytes.len() {
+            if self.len == N {
+                self.try_flush_sendbuffer()?;
+            }
+            let n = core::cmp::min(N - self.len, bytes.len() - cursor);
+            self.buffer[self.len..self.len + n].copy_from_slice(&bytes[cursor..cursor + n]);
+            self.len += n;
+            cursor += n;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, TCS> StackAndSocket<'a, TCS>
+where
+    TCS: TcpClientStack,
+{
+    /// wrap this [StackAndSocket] with an `N`-byte staging buffer that batches writes into
+    /// fewer calls to `send`. See [BufferedStackAndSocket].
+    pub fn buffered<const N: usize>(self) -> BufferedStackAndSocket<'a, TCS, N> {
+        BufferedStackAndSocket::new(self)
+    }
+}
+
+impl<'a, TCS, const N: usize> ufmt::uWrite for BufferedStackAndSocket<'a, TCS, N>
+where
+    TCS: TcpClientStack,
+{
+    type Error = TCS::Error;
+    fn write_str(&mut self, message: &str) -> Result<(), <Self as ufmt::uWrite>::Error> {
+        self.stage(message.as_bytes())
+    }
+}
+
+/// Wraps a `TCS::Error` so it can satisfy [embedded_io::Error], classifying a peer-closed
+/// connection as [ErrorKind::ConnectionReset] via [TcpError::kind] rather than [ErrorKind::Other].
+#[derive(Debug)]
+pub struct IoError<E>(pub E);
+
+impl<E: TcpError + core::fmt::Debug> embedded_io::Error for IoError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self.0.kind() {
+            TcpErrorKind::PipeClosed => ErrorKind::ConnectionReset,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl<'a, TCS> embedded_io::ErrorType for StackAndSocket<'a, TCS>
+where
+    TCS: TcpClientStack,
+    TCS::Error: TcpError,
+{
+    type Error = IoError<TCS::Error>;
+}
+
+impl<'a, TCS> embedded_io::Read for StackAndSocket<'a, TCS>
+where
+    TCS: TcpClientStack,
+    TCS::Error: TcpError,
+{
+    /// Read into `buf`, blocking until at least one byte has arrived.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        nb::block!(self.tcp_stack.receive(self.socket, buf)).map_err(IoError)
+    }
+}
+
+impl<'a, TCS> embedded_io::Write for StackAndSocket<'a, TCS>
+where
+    TCS: TcpClientStack,
+    TCS::Error: TcpError,
+{
+    /// Write `buf`, blocking until at least one byte has been sent.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        nb::block!(self.tcp_stack.send(self.socket, buf)).map_err(IoError)
+    }
+
+    /// a no-op: [StackAndSocket] has no staging buffer of its own. See
+    /// [BufferedStackAndSocket]'s `embedded_io::Write` impl for a flush that actually drains one.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a, TCS> embedded_io::ReadReady for StackAndSocket<'a, TCS>
+where
+    TCS: TcpClientStack,
+    TCS::Error: TcpError,
+{
+    /// Check whether the next [embedded_io::Read::read] would block.
+    ///
+    /// This polls with a non-blocking, zero-length `receive`. As with [Self::is_closed], stacks
+    /// that special-case a zero-length read as a no-op `Ok(0)` rather than reflecting real socket
+    /// state always report `true`, even when no data is waiting.
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        match self.tcp_stack.receive(self.socket, &mut []) {
+            Ok(_) => Ok(true),
+            Err(nb::Error::WouldBlock) => Ok(false),
+            Err(nb::Error::Other(e)) => Err(IoError(e)),
+        }
+    }
+}
+
+impl<'a, TCS, const N: usize> embedded_io::ErrorType for BufferedStackAndSocket<'a, TCS, N>
+where
+    TCS: TcpClientStack,
+    TCS::Error: TcpError,
+{
+    type Error = IoError<TCS::Error>;
+}
+
+impl<'a, TCS, const N: usize> embedded_io::Write for BufferedStackAndSocket<'a, TCS, N>
+where
+    TCS: TcpClientStack,
+    TCS::Error: TcpError,
+{
+    /// Stage `buf`, flushing to the socket whenever the accumulator fills.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.stage(buf).map_err(IoError)?;
+        Ok(buf.len())
+    }
+
+    /// Drain the accumulator, blocking until it is empty.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        nb::block!(self.flush()).map_err(IoError)
+    }
+}
+
+/// The UDP counterpart to [TcpClientStackPlus].
+pub trait UdpClientStackPlus<UCS: UdpClientStack> {
+    /// create a tuple referencing the [UdpClientStack] and a `UdpSocket` that has all the information necessary to send and receive datagrams.
+    fn with_udp_socket<'a>(
+        &'a mut self,
+        socket: &'a mut UCS::UdpSocket,
+    ) -> UdpStackAndSocket<'a, UCS>;
+}
+
+impl<UCS: UdpClientStack> UdpClientStackPlus<UCS> for UCS {
+    /// create a tuple referencing the [UdpClientStack] and a `UdpSocket` that has all the information necessary to send and receive datagrams.
+    fn with_udp_socket<'a>(
+        &'a mut self,
+        socket: &'a mut UCS::UdpSocket,
+    ) -> UdpStackAndSocket<'a, UCS>
+    where
+        Self: Sized,
+    {
+        UdpStackAndSocket::new(self, socket)
+    }
+}
+
+/// The UDP counterpart to [StackAndSocket]. This tuple combines a reference to the
+/// [UdpClientStack] and a `UdpSocket` into a single entity that knows enough to send and
+/// receive datagrams. It is intended to be an ephemeral construction, mirroring the design of
+/// [StackAndSocket].
+pub struct UdpStackAndSocket<'a, UCS>
+where
+    UCS: UdpClientStack,
+{
+    /// the [UdpClientStack] needed to perform operations on the [Self::socket]
+    pub udp_stack: &'a mut UCS,
+    /// a `UdpSocket` usable with the [Self::udp_stack] to send and receive datagrams.
+    pub socket: &'a mut UCS::UdpSocket,
+}
+
+impl<'a, UCS> UdpStackAndSocket<'a, UCS>
+where
+    UCS: UdpClientStack,
+{
+    /// create a new [UdpStackAndSocket] from the stack reference and socket reference
+    pub fn new(udp_stack: &'a mut UCS, socket: &'a mut UCS::UdpSocket) -> Self {
+        UdpStackAndSocket { udp_stack, socket }
+    }
+
+    /// Connect this socket to `remote`, so that [Self::send] and [Self::receive] address it
+    /// implicitly.
+    pub fn connect(&mut self, remote: SocketAddr) -> Result<(), UCS::Error> {
+        self.udp_stack.connect(self.socket, remote)
+    }
+
+    /// Send `buffer` as a single datagram to the remote address set by [Self::connect].
+    pub fn send(&mut self, buffer: &[u8]) -> nb::Result<(), UCS::Error> {
+        self.udp_stack.send(self.socket, buffer)
+    }
+
+    /// Receive a datagram into `buffer`.
+    ///
+    /// Returns the number of bytes received along with the remote address it came from, or
+    /// [`nb::Error::WouldBlock`] if no datagram has arrived yet.
+    pub fn receive(&mut self, buffer: &mut [u8]) -> nb::Result<(usize, SocketAddr), UCS::Error> {
+        self.udp_stack.receive(self.socket, buffer)
+    }
+}
+
+impl<'a, UCS> UdpStackAndSocket<'a, UCS>
+where
+    UCS: UdpFullStack,
+{
+    /// Send `buffer` as a single datagram to `remote`, without requiring a prior
+    /// [Self::connect].
+    pub fn send_to(&mut self, remote: SocketAddr, buffer: &[u8]) -> nb::Result<(), UCS::Error> {
+        self.udp_stack.send_to(self.socket, remote, buffer)
+    }
+}
+
+impl<'a, UCS> ufmt::uWrite for UdpStackAndSocket<'a, UCS>
+where
+    UCS: UdpClientStack,
+{
+    type Error = UCS::Error;
+    fn write_str(&mut self, message: &str) -> Result<(), <Self as ufmt::uWrite>::Error> {
+        nb::block!(self.udp_stack.send(self.socket, message.as_bytes()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::cell::{Cell, RefCell};
+    use embedded_nal::{IpAddr, Ipv4Addr};
+    use std::vec::Vec;
+
     #[test]
     fn it_works() {
         let result = 2 + 2;
         assert_eq!(result, 4);
     }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum MockErrorKind {
+        Closed,
+        Other,
+    }
+
+    #[derive(Debug, Clone)]
+    struct MockError(MockErrorKind);
+
+    impl TcpError for MockError {
+        fn kind(&self) -> TcpErrorKind {
+            match self.0 {
+                MockErrorKind::Closed => TcpErrorKind::PipeClosed,
+                MockErrorKind::Other => TcpErrorKind::Other,
+            }
+        }
+    }
+
+    /// A [TcpClientStack] double that records every byte ever passed to `send`, and how many
+    /// times `send` was called, so tests can check batching behavior.
+    struct RecordingStack {
+        sent: RefCell<Vec<u8>>,
+        send_calls: Cell<usize>,
+    }
+
+    impl RecordingStack {
+        fn new() -> Self {
+            RecordingStack {
+                sent: RefCell::new(Vec::new()),
+                send_calls: Cell::new(0),
+            }
+        }
+    }
+
+    impl TcpClientStack for RecordingStack {
+        type TcpSocket = ();
+        type Error = MockError;
+
+        fn socket(&mut self) -> Result<(), MockError> {
+            Ok(())
+        }
+
+        fn connect(&mut self, _socket: &mut (), _remote: SocketAddr) -> nb::Result<(), MockError> {
+            Ok(())
+        }
+
+        fn send(&mut self, _socket: &mut (), buffer: &[u8]) -> nb::Result<usize, MockError> {
+            self.send_calls.set(self.send_calls.get() + 1);
+            self.sent.borrow_mut().extend_from_slice(buffer);
+            Ok(buffer.len())
+        }
+
+        fn receive(
+            &mut self,
+            _socket: &mut (),
+            _buffer: &mut [u8],
+        ) -> nb::Result<usize, MockError> {
+            Err(nb::Error::WouldBlock)
+        }
+
+        fn close(&mut self, _socket: ()) -> Result<(), MockError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn buffered_coalesces_writes_and_flush_drains_remainder() {
+        let mut stack = RecordingStack::new();
+        let mut socket = ();
+        {
+            let sas = StackAndSocket::new(&mut stack, &mut socket);
+            let mut buffered = sas.buffered::<4>();
+            ufmt::uwrite!(buffered, "he").unwrap();
+            ufmt::uwrite!(buffered, "l").unwrap();
+            ufmt::uwrite!(buffered, "lo").unwrap();
+            nb::block!(buffered.flush()).unwrap();
+        }
+        // a 4-byte accumulator flushing 5 bytes should need one send() to drain the full buffer
+        // plus one more for the single leftover byte, rather than one send() per write_str call.
+        assert_eq!(stack.sent.borrow().as_slice(), b"hello");
+        assert_eq!(stack.send_calls.get(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero buffer size")]
+    fn buffered_rejects_zero_size_buffer() {
+        let mut stack = RecordingStack::new();
+        let mut socket = ();
+        let sas = StackAndSocket::new(&mut stack, &mut socket);
+        let _ = sas.buffered::<0>();
+    }
+
+    /// A [TcpClientStack] double whose `send` always fails with a fixed, scriptable error.
+    struct AlwaysErrTcp {
+        error: MockErrorKind,
+    }
+
+    impl TcpClientStack for AlwaysErrTcp {
+        type TcpSocket = ();
+        type Error = MockError;
+
+        fn socket(&mut self) -> Result<(), MockError> {
+            Ok(())
+        }
+
+        fn connect(&mut self, _socket: &mut (), _remote: SocketAddr) -> nb::Result<(), MockError> {
+            Ok(())
+        }
+
+        fn send(&mut self, _socket: &mut (), _buffer: &[u8]) -> nb::Result<usize, MockError> {
+            Err(nb::Error::Other(MockError(self.error)))
+        }
+
+        fn receive(
+            &mut self,
+            _socket: &mut (),
+            _buffer: &mut [u8],
+        ) -> nb::Result<usize, MockError> {
+            Err(nb::Error::WouldBlock)
+        }
+
+        fn close(&mut self, _socket: ()) -> Result<(), MockError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn fmt_write_stashes_error_for_take_error() {
+        let mut stack = AlwaysErrTcp {
+            error: MockErrorKind::Other,
+        };
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        use core::fmt::Write;
+        assert!(write!(sas, "x").is_err());
+        assert!(matches!(sas.take_error(), Some(MockError(MockErrorKind::Other))));
+    }
+
+    /// A stack that resolves DNS lookups with a fixed, scriptable result and connects to any
+    /// address, optionally reporting `WouldBlock` on the first `connect` attempt.
+    /// Counts `get_host_by_name` calls so tests can check it is not re-invoked on every poll.
+    struct DnsStack {
+        dns_result: Result<IpAddr, MockError>,
+        dns_calls: Cell<usize>,
+        connect_would_block_once: Cell<bool>,
+    }
+
+    impl TcpClientStack for DnsStack {
+        type TcpSocket = ();
+        type Error = MockError;
+
+        fn socket(&mut self) -> Result<(), MockError> {
+            Ok(())
+        }
+
+        fn connect(&mut self, _socket: &mut (), _remote: SocketAddr) -> nb::Result<(), MockError> {
+            if self.connect_would_block_once.take() {
+                Err(nb::Error::WouldBlock)
+            } else {
+                Ok(())
+            }
+        }
+
+        fn send(&mut self, _socket: &mut (), buffer: &[u8]) -> nb::Result<usize, MockError> {
+            Ok(buffer.len())
+        }
+
+        fn receive(
+            &mut self,
+            _socket: &mut (),
+            _buffer: &mut [u8],
+        ) -> nb::Result<usize, MockError> {
+            Err(nb::Error::WouldBlock)
+        }
+
+        fn close(&mut self, _socket: ()) -> Result<(), MockError> {
+            Ok(())
+        }
+    }
+
+    impl Dns for DnsStack {
+        type Error = MockError;
+
+        fn get_host_by_name(
+            &mut self,
+            _hostname: &str,
+            _addr_type: AddrType,
+        ) -> nb::Result<IpAddr, MockError> {
+            self.dns_calls.set(self.dns_calls.get() + 1);
+            self.dns_result.clone().map_err(nb::Error::Other)
+        }
+
+        fn get_host_by_address(&self, _addr: IpAddr, _result: &mut [u8]) -> Result<usize, MockError> {
+            unimplemented!("not exercised by these tests")
+        }
+    }
+
+    #[test]
+    fn connect_host_surfaces_dns_failure_as_dns_variant() {
+        let mut stack = DnsStack {
+            dns_result: Err(MockError(MockErrorKind::Other)),
+            dns_calls: Cell::new(0),
+            connect_would_block_once: Cell::new(false),
+        };
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        let err = sas
+            .connect_host("example.invalid", 80, AddrType::IPv4)
+            .unwrap_err();
+        assert!(matches!(err, nb::Error::Other(ConnectHostError::Dns(_))));
+    }
+
+    #[test]
+    fn connect_host_connects_to_the_resolved_address() {
+        let mut stack = DnsStack {
+            dns_result: Ok(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+            dns_calls: Cell::new(0),
+            connect_would_block_once: Cell::new(false),
+        };
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        assert!(sas.connect_host("example.invalid", 80, AddrType::IPv4).is_ok());
+    }
+
+    #[test]
+    fn connect_host_does_not_re_resolve_dns_while_connect_is_pending() {
+        let mut stack = DnsStack {
+            dns_result: Ok(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+            dns_calls: Cell::new(0),
+            connect_would_block_once: Cell::new(true),
+        };
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+
+        // first poll: DNS resolves, then connect() reports WouldBlock.
+        assert!(matches!(
+            sas.connect_host("example.invalid", 80, AddrType::IPv4),
+            Err(nb::Error::WouldBlock)
+        ));
+        assert_eq!(sas.tcp_stack.dns_calls.get(), 1);
+
+        // second poll: connect() succeeds without re-resolving the hostname.
+        assert!(sas.connect_host("example.invalid", 80, AddrType::IPv4).is_ok());
+        assert_eq!(sas.tcp_stack.dns_calls.get(), 1);
+    }
+
+    /// A [TcpClientStack] double whose `send` result is scripted once via a `Cell`, defaulting
+    /// to a successful write of the whole buffer.
+    struct ScriptedSendTcp {
+        next_send_error: Cell<Option<MockErrorKind>>,
+    }
+
+    impl TcpClientStack for ScriptedSendTcp {
+        type TcpSocket = ();
+        type Error = MockError;
+
+        fn socket(&mut self) -> Result<(), MockError> {
+            Ok(())
+        }
+
+        fn connect(&mut self, _socket: &mut (), _remote: SocketAddr) -> nb::Result<(), MockError> {
+            Ok(())
+        }
+
+        fn send(&mut self, _socket: &mut (), buffer: &[u8]) -> nb::Result<usize, MockError> {
+            match self.next_send_error.take() {
+                None => Ok(buffer.len()),
+                Some(kind) => Err(nb::Error::Other(MockError(kind))),
+            }
+        }
+
+        fn receive(
+            &mut self,
+            _socket: &mut (),
+            _buffer: &mut [u8],
+        ) -> nb::Result<usize, MockError> {
+            Err(nb::Error::WouldBlock)
+        }
+
+        fn close(&mut self, _socket: ()) -> Result<(), MockError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn send_all_reports_closed_on_pipe_closed_error() {
+        let mut stack = ScriptedSendTcp {
+            next_send_error: Cell::new(Some(MockErrorKind::Closed)),
+        };
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        assert!(matches!(sas.send_all(b"x"), Err(SendAllError::Closed)));
+    }
+
+    #[test]
+    fn send_all_bubbles_up_other_errors() {
+        let mut stack = ScriptedSendTcp {
+            next_send_error: Cell::new(Some(MockErrorKind::Other)),
+        };
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        assert!(matches!(sas.send_all(b"x"), Err(SendAllError::Other(_))));
+    }
+
+    #[test]
+    fn is_closed_true_when_send_reports_pipe_closed() {
+        let mut stack = ScriptedSendTcp {
+            next_send_error: Cell::new(Some(MockErrorKind::Closed)),
+        };
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        assert!(sas.is_closed());
+    }
+
+    /// A [TcpClientStack] double whose `send`/`receive` always report [`nb::Error::WouldBlock`].
+    struct AlwaysBlockTcp;
+
+    impl TcpClientStack for AlwaysBlockTcp {
+        type TcpSocket = ();
+        type Error = MockError;
+
+        fn socket(&mut self) -> Result<(), MockError> {
+            Ok(())
+        }
+
+        fn connect(&mut self, _socket: &mut (), _remote: SocketAddr) -> nb::Result<(), MockError> {
+            Ok(())
+        }
+
+        fn send(&mut self, _socket: &mut (), _buffer: &[u8]) -> nb::Result<usize, MockError> {
+            Err(nb::Error::WouldBlock)
+        }
+
+        fn receive(
+            &mut self,
+            _socket: &mut (),
+            _buffer: &mut [u8],
+        ) -> nb::Result<usize, MockError> {
+            Err(nb::Error::WouldBlock)
+        }
+
+        fn close(&mut self, _socket: ()) -> Result<(), MockError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn receive_mode_timeout_elapses() {
+        let mut stack = AlwaysBlockTcp;
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        let mut buffer = [0u8; 4];
+        let mut now = 0u64;
+        let result = sas.receive_mode(&mut buffer, Mode::Timeout(10), || {
+            now += 5;
+            now
+        });
+        assert!(matches!(result, Err(ModeError::TimedOut)));
+    }
+
+    #[test]
+    fn send_mode_non_blocking_reports_would_block_immediately() {
+        let mut stack = AlwaysBlockTcp;
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        let result = sas.send_mode(b"x", Mode::NonBlocking, || 0);
+        assert!(matches!(result, Err(ModeError::WouldBlock)));
+    }
+
+    #[test]
+    fn embedded_io_write_maps_pipe_closed_to_connection_reset() {
+        let mut stack = ScriptedSendTcp {
+            next_send_error: Cell::new(Some(MockErrorKind::Closed)),
+        };
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        let err = embedded_io::Write::write(&mut sas, b"x").unwrap_err();
+        assert_eq!(
+            embedded_io::Error::kind(&err),
+            embedded_io::ErrorKind::ConnectionReset
+        );
+    }
+
+    #[test]
+    fn read_ready_reports_false_while_would_block() {
+        let mut stack = AlwaysBlockTcp;
+        let mut socket = ();
+        let mut sas = StackAndSocket::new(&mut stack, &mut socket);
+        assert!(matches!(embedded_io::ReadReady::read_ready(&mut sas), Ok(false)));
+    }
+
+    /// A [UdpClientStack] double that records sent datagrams and always yields a fixed
+    /// datagram from a fixed remote address on `receive`.
+    struct MockUdp {
+        sent: RefCell<Vec<u8>>,
+    }
+
+    impl UdpClientStack for MockUdp {
+        type UdpSocket = ();
+        type Error = MockError;
+
+        fn socket(&mut self) -> Result<(), MockError> {
+            Ok(())
+        }
+
+        fn connect(&mut self, _socket: &mut (), _remote: SocketAddr) -> Result<(), MockError> {
+            Ok(())
+        }
+
+        fn send(&mut self, _socket: &mut (), buffer: &[u8]) -> nb::Result<(), MockError> {
+            self.sent.borrow_mut().extend_from_slice(buffer);
+            Ok(())
+        }
+
+        fn receive(
+            &mut self,
+            _socket: &mut (),
+            buffer: &mut [u8],
+        ) -> nb::Result<(usize, SocketAddr), MockError> {
+            let remote = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 9);
+            buffer[..5].copy_from_slice(b"hello");
+            Ok((5, remote))
+        }
+
+        fn close(&mut self, _socket: ()) -> Result<(), MockError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn udp_send_and_receive_round_trip() {
+        let mut stack = MockUdp {
+            sent: RefCell::new(Vec::new()),
+        };
+        let mut socket = ();
+        let mut uas = UdpStackAndSocket::new(&mut stack, &mut socket);
+        uas.send(b"ping").unwrap();
+        assert_eq!(uas.udp_stack.sent.borrow().as_slice(), b"ping");
+
+        let mut buffer = [0u8; 5];
+        let (n, remote) = uas.receive(&mut buffer).unwrap();
+        assert_eq!(&buffer[..n], b"hello");
+        assert_eq!(
+            remote,
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 9)
+        );
+    }
 }